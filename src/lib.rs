@@ -1,7 +1,47 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 use serde_json::Value;
 
+enum NumberPushable {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+}
+
+fn is_vector_type(t: flexbuffers::FlexBufferType) -> bool {
+    matches!(
+        t,
+        flexbuffers::FlexBufferType::Vector
+            | flexbuffers::FlexBufferType::VectorInt
+            | flexbuffers::FlexBufferType::VectorUInt
+            | flexbuffers::FlexBufferType::VectorFloat
+            | flexbuffers::FlexBufferType::VectorBool
+            | flexbuffers::FlexBufferType::VectorKey
+            | flexbuffers::FlexBufferType::VectorString
+            | flexbuffers::FlexBufferType::VectorInt2
+            | flexbuffers::FlexBufferType::VectorInt3
+            | flexbuffers::FlexBufferType::VectorInt4
+            | flexbuffers::FlexBufferType::VectorUInt2
+            | flexbuffers::FlexBufferType::VectorUInt3
+            | flexbuffers::FlexBufferType::VectorUInt4
+            | flexbuffers::FlexBufferType::VectorFloat2
+            | flexbuffers::FlexBufferType::VectorFloat3
+            | flexbuffers::FlexBufferType::VectorFloat4
+    )
+}
+
+#[napi(object)]
+pub struct FlexBufferOptions {
+    pub share_keys: Option<bool>,
+    /// flexbuffers 2.0.0 marks string sharing "Not Yet Implemented" upstream —
+    /// `Builder::new` doesn't branch on this flag yet, so setting it is currently a no-op.
+    pub share_strings: Option<bool>,
+    /// flexbuffers 2.0.0 marks key-vector sharing "Not Yet Implemented" upstream —
+    /// `Builder::new` doesn't branch on this flag yet, so setting it is currently a no-op.
+    pub share_key_vectors: Option<bool>,
+}
+
 #[napi]
 pub struct FlexBuffer {
     data: Vec<u8>,
@@ -23,6 +63,31 @@ impl FlexBuffer {
         Ok(())
     }
 
+    #[napi]
+    pub fn serialize_with_options(
+        &mut self,
+        value: serde_json::Value,
+        options: FlexBufferOptions,
+    ) -> Result<()> {
+        let mut flags = flexbuffers::BuilderOptions::empty();
+        if options.share_keys.unwrap_or(true) {
+            flags |= flexbuffers::BuilderOptions::SHARE_KEYS;
+        }
+        // Forwarded for forward-compatibility, but currently no-ops: flexbuffers 2.0.0
+        // hasn't implemented string/key-vector sharing in `Builder::new` yet.
+        if options.share_strings.unwrap_or(false) {
+            flags |= flexbuffers::BuilderOptions::SHARE_STRINGS;
+        }
+        if options.share_key_vectors.unwrap_or(false) {
+            flags |= flexbuffers::BuilderOptions::SHARE_KEY_VECTORS;
+        }
+
+        let mut builder = flexbuffers::Builder::new(flags);
+        self.serialize_value(&mut builder, &value)?;
+        self.data = builder.take_buffer();
+        Ok(())
+    }
+
     #[napi]
     pub fn deserialize(&self) -> Result<serde_json::Value> {
         if self.data.is_empty() {
@@ -53,6 +118,242 @@ impl FlexBuffer {
         self.data.len() as u32
     }
 
+    #[napi]
+    pub fn get_path(&self, path: Vec<String>) -> Result<serde_json::Value> {
+        let reader = self.resolve_path(&path)?;
+        self.deserialize_value(&reader)
+    }
+
+    #[napi]
+    pub fn type_at(&self, path: Vec<String>) -> Result<String> {
+        let reader = self.resolve_path(&path)?;
+        Ok(format!("{:?}", reader.flexbuffer_type()))
+    }
+
+    #[napi]
+    pub fn length(&self, path: Vec<String>) -> Result<u32> {
+        let reader = self.resolve_path(&path)?;
+        match reader.flexbuffer_type() {
+            t if is_vector_type(t) => Ok(reader.as_vector().len() as u32),
+            flexbuffers::FlexBufferType::Map => Ok(reader.as_map().len() as u32),
+            flexbuffers::FlexBufferType::String => Ok(reader.as_str().len() as u32),
+            flexbuffers::FlexBufferType::Blob => Ok(reader.as_blob().0.len() as u32),
+            other => Err(Error::new(
+                Status::InvalidArg,
+                format!("{:?} has no length", other),
+            )),
+        }
+    }
+
+    fn resolve_path<'a>(&'a self, path: &[String]) -> Result<flexbuffers::Reader<&'a [u8]>> {
+        if self.data.is_empty() {
+            return Err(Error::new(Status::InvalidArg, "Buffer is empty"));
+        }
+
+        let mut reader = flexbuffers::Reader::get_root(&self.data[..])
+            .map_err(|e| Error::new(Status::GenericFailure, e.to_string()))?;
+
+        for segment in path {
+            reader = match reader.flexbuffer_type() {
+                flexbuffers::FlexBufferType::Map => reader.as_map().idx(segment.as_str()),
+                t if is_vector_type(t) => {
+                    let index: usize = segment.parse().map_err(|_| {
+                        Error::new(
+                            Status::InvalidArg,
+                            format!("Invalid array index: {}", segment),
+                        )
+                    })?;
+                    reader.as_vector().idx(index)
+                }
+                other => {
+                    return Err(Error::new(
+                        Status::InvalidArg,
+                        format!("Cannot index into {:?} with '{}'", other, segment),
+                    ))
+                }
+            };
+        }
+
+        Ok(reader)
+    }
+
+    #[napi]
+    pub fn to_json_string(&self, pretty: Option<bool>) -> Result<String> {
+        if self.data.is_empty() {
+            return Err(Error::new(Status::InvalidArg, "Buffer is empty"));
+        }
+
+        let root = flexbuffers::Reader::get_root(&self.data[..])
+            .map_err(|e| Error::new(Status::GenericFailure, e.to_string()))?;
+
+        let indent = if pretty.unwrap_or(false) { Some(0) } else { None };
+        let mut out = String::new();
+        self.write_json(&root, &mut out, indent)?;
+        Ok(out)
+    }
+
+    #[napi]
+    pub fn from_json_string(text: String) -> Result<FlexBuffer> {
+        let value: serde_json::Value = serde_json::from_str(&text)
+            .map_err(|e| Error::new(Status::InvalidArg, format!("Invalid JSON: {}", e)))?;
+
+        let mut fb = FlexBuffer::new();
+        fb.serialize(value)?;
+        Ok(fb)
+    }
+
+    fn write_json(
+        &self,
+        reader: &flexbuffers::Reader<&[u8]>,
+        out: &mut String,
+        indent: Option<usize>,
+    ) -> Result<()> {
+        match reader.flexbuffer_type() {
+            flexbuffers::FlexBufferType::Null => out.push_str("null"),
+            flexbuffers::FlexBufferType::Bool => {
+                out.push_str(if reader.as_bool() { "true" } else { "false" })
+            }
+            flexbuffers::FlexBufferType::Int => out.push_str(&reader.as_i64().to_string()),
+            flexbuffers::FlexBufferType::UInt => out.push_str(&reader.as_u64().to_string()),
+            flexbuffers::FlexBufferType::Float => {
+                let n = serde_json::Number::from_f64(reader.as_f64()).unwrap_or(0.into());
+                out.push_str(&n.to_string());
+            }
+            flexbuffers::FlexBufferType::String => {
+                out.push_str(&Self::json_escape(reader.as_str())?);
+            }
+            flexbuffers::FlexBufferType::Blob => {
+                let blob = reader.as_blob();
+                out.push_str(&Self::json_escape(&STANDARD.encode(blob.0))?);
+            }
+            t if is_vector_type(t) => {
+                let vec = reader.as_vector();
+                out.push('[');
+                for i in 0..vec.len() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    Self::write_newline_indent(out, indent.map(|n| n + 1));
+                    self.write_json(&vec.idx(i), out, indent.map(|n| n + 1))?;
+                }
+                if !vec.is_empty() {
+                    Self::write_newline_indent(out, indent);
+                }
+                out.push(']');
+            }
+            flexbuffers::FlexBufferType::Map => {
+                let map = reader.as_map();
+                let keys = map.keys_vector();
+                out.push('{');
+                for i in 0..map.len() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    Self::write_newline_indent(out, indent.map(|n| n + 1));
+                    out.push_str(&Self::json_escape(keys.idx(i).as_str())?);
+                    out.push(':');
+                    if indent.is_some() {
+                        out.push(' ');
+                    }
+                    self.write_json(&map.idx(i), out, indent.map(|n| n + 1))?;
+                }
+                if !map.is_empty() {
+                    Self::write_newline_indent(out, indent);
+                }
+                out.push('}');
+            }
+            other => {
+                return Err(Error::new(
+                    Status::GenericFailure,
+                    format!("Unsupported flexbuffer type: {:?}", other),
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    fn write_newline_indent(out: &mut String, indent: Option<usize>) {
+        if let Some(level) = indent {
+            out.push('\n');
+            for _ in 0..level {
+                out.push_str("  ");
+            }
+        }
+    }
+
+    fn json_escape(s: &str) -> Result<String> {
+        serde_json::to_string(s).map_err(|e| Error::new(Status::GenericFailure, e.to_string()))
+    }
+
+    #[napi]
+    pub fn serialize_blob(&mut self, data: Buffer) -> Result<()> {
+        let mut builder = flexbuffers::Builder::default();
+        builder.build_singleton(flexbuffers::Blob(data.as_ref()));
+        self.data = builder.take_buffer();
+        Ok(())
+    }
+
+    #[napi]
+    pub fn get_blob(&self) -> Result<Buffer> {
+        if self.data.is_empty() {
+            return Err(Error::new(Status::InvalidArg, "Buffer is empty"));
+        }
+
+        let root = flexbuffers::Reader::get_root(&self.data[..])
+            .map_err(|e| Error::new(Status::GenericFailure, e.to_string()))?;
+
+        if root.flexbuffer_type() != flexbuffers::FlexBufferType::Blob {
+            return Err(Error::new(Status::InvalidArg, "Root value is not a blob"));
+        }
+
+        Ok(root.as_blob().0.to_vec().into())
+    }
+
+    #[napi]
+    pub fn serialize_big_int(&mut self, value: BigInt) -> Result<()> {
+        let mut builder = flexbuffers::Builder::default();
+        if value.sign_bit {
+            let (i, lossless) = value.get_i64();
+            if !lossless {
+                return Err(Error::new(
+                    Status::InvalidArg,
+                    "BigInt does not fit in a 64-bit signed integer",
+                ));
+            }
+            builder.build_singleton(i);
+        } else {
+            let (_, u, lossless) = value.get_u64();
+            if !lossless {
+                return Err(Error::new(
+                    Status::InvalidArg,
+                    "BigInt does not fit in a 64-bit unsigned integer",
+                ));
+            }
+            builder.build_singleton(u);
+        }
+        self.data = builder.take_buffer();
+        Ok(())
+    }
+
+    #[napi]
+    pub fn get_big_int(&self) -> Result<BigInt> {
+        if self.data.is_empty() {
+            return Err(Error::new(Status::InvalidArg, "Buffer is empty"));
+        }
+
+        let root = flexbuffers::Reader::get_root(&self.data[..])
+            .map_err(|e| Error::new(Status::GenericFailure, e.to_string()))?;
+
+        match root.flexbuffer_type() {
+            flexbuffers::FlexBufferType::Int => Ok(BigInt::from(root.as_i64())),
+            flexbuffers::FlexBufferType::UInt => Ok(BigInt::from(root.as_u64())),
+            other => Err(Error::new(
+                Status::InvalidArg,
+                format!("Root value is not an integer: {:?}", other),
+            )),
+        }
+    }
+
     fn serialize_value(&self, builder: &mut flexbuffers::Builder, value: &Value) -> Result<()> {
         match value {
             Value::Null => {
@@ -61,65 +362,31 @@ impl FlexBuffer {
             Value::Bool(b) => {
                 builder.build_singleton(*b);
             }
-            Value::Number(n) => {
-                if let Some(i) = n.as_i64() {
+            Value::Number(n) => match Self::number_as_pushable(n)? {
+                NumberPushable::Int(i) => {
                     builder.build_singleton(i);
-                } else if let Some(f) = n.as_f64() {
+                }
+                NumberPushable::UInt(u) => {
+                    builder.build_singleton(u);
+                }
+                NumberPushable::Float(f) => {
                     builder.build_singleton(f);
-                } else {
-                    return Err(Error::new(Status::InvalidArg, "Invalid number"));
                 }
-            }
+            },
             Value::String(s) => {
                 builder.build_singleton(s.as_str());
             }
             Value::Array(arr) => {
                 let mut vec = builder.start_vector();
                 for item in arr {
-                    match item {
-                        Value::Null => vec.push(()),
-                        Value::Bool(b) => vec.push(*b),
-                        Value::Number(n) => {
-                            if let Some(i) = n.as_i64() {
-                                vec.push(i);
-                            } else if let Some(f) = n.as_f64() {
-                                vec.push(f);
-                            }
-                        }
-                        Value::String(s) => vec.push(s.as_str()),
-                        Value::Array(_) | Value::Object(_) => {
-                            // For complex nested types, we need to serialize them recursively
-                            // This is a limitation of the current flexbuffers 2.0 API
-                            // For now, we'll convert them to strings as a workaround
-                            let json_str = serde_json::to_string(item)
-                                .map_err(|e| Error::new(Status::GenericFailure, e.to_string()))?;
-                            vec.push(json_str.as_str());
-                        }
-                    }
+                    self.serialize_into_vector(&mut vec, item)?;
                 }
                 vec.end_vector();
             }
             Value::Object(obj) => {
                 let mut map = builder.start_map();
                 for (key, val) in obj {
-                    match val {
-                        Value::Null => map.push(key.as_str(), ()),
-                        Value::Bool(b) => map.push(key.as_str(), *b),
-                        Value::Number(n) => {
-                            if let Some(i) = n.as_i64() {
-                                map.push(key.as_str(), i);
-                            } else if let Some(f) = n.as_f64() {
-                                map.push(key.as_str(), f);
-                            }
-                        }
-                        Value::String(s) => map.push(key.as_str(), s.as_str()),
-                        Value::Array(_) | Value::Object(_) => {
-                            // For complex nested types, serialize as JSON string for now
-                            let json_str = serde_json::to_string(val)
-                                .map_err(|e| Error::new(Status::GenericFailure, e.to_string()))?;
-                            map.push(key.as_str(), json_str.as_str());
-                        }
-                    }
+                    self.serialize_into_map(&mut map, key, val)?;
                 }
                 map.end_map();
             }
@@ -127,47 +394,97 @@ impl FlexBuffer {
         Ok(())
     }
 
+    fn serialize_into_vector(
+        &self,
+        vec: &mut flexbuffers::VectorBuilder,
+        value: &Value,
+    ) -> Result<()> {
+        match value {
+            Value::Null => vec.push(()),
+            Value::Bool(b) => vec.push(*b),
+            Value::Number(n) => match Self::number_as_pushable(n)? {
+                NumberPushable::Int(i) => vec.push(i),
+                NumberPushable::UInt(u) => vec.push(u),
+                NumberPushable::Float(f) => vec.push(f),
+            },
+            Value::String(s) => vec.push(s.as_str()),
+            Value::Array(arr) => {
+                let mut child = vec.start_vector();
+                for item in arr {
+                    self.serialize_into_vector(&mut child, item)?;
+                }
+                child.end_vector();
+            }
+            Value::Object(obj) => {
+                let mut child = vec.start_map();
+                for (key, val) in obj {
+                    self.serialize_into_map(&mut child, key, val)?;
+                }
+                child.end_map();
+            }
+        }
+        Ok(())
+    }
+
+    fn serialize_into_map(
+        &self,
+        map: &mut flexbuffers::MapBuilder,
+        key: &str,
+        value: &Value,
+    ) -> Result<()> {
+        match value {
+            Value::Null => map.push(key, ()),
+            Value::Bool(b) => map.push(key, *b),
+            Value::Number(n) => match Self::number_as_pushable(n)? {
+                NumberPushable::Int(i) => map.push(key, i),
+                NumberPushable::UInt(u) => map.push(key, u),
+                NumberPushable::Float(f) => map.push(key, f),
+            },
+            Value::String(s) => map.push(key, s.as_str()),
+            Value::Array(arr) => {
+                let mut child = map.start_vector(key);
+                for item in arr {
+                    self.serialize_into_vector(&mut child, item)?;
+                }
+                child.end_vector();
+            }
+            Value::Object(obj) => {
+                let mut child = map.start_map(key);
+                for (k, v) in obj {
+                    self.serialize_into_map(&mut child, k, v)?;
+                }
+                child.end_map();
+            }
+        }
+        Ok(())
+    }
+
+    fn number_as_pushable(n: &serde_json::Number) -> Result<NumberPushable> {
+        if let Some(i) = n.as_i64() {
+            Ok(NumberPushable::Int(i))
+        } else if let Some(u) = n.as_u64() {
+            Ok(NumberPushable::UInt(u))
+        } else if let Some(f) = n.as_f64() {
+            Ok(NumberPushable::Float(f))
+        } else {
+            Err(Error::new(Status::InvalidArg, "Invalid number"))
+        }
+    }
+
     fn deserialize_value(&self, reader: &flexbuffers::Reader<&[u8]>) -> Result<Value> {
         match reader.flexbuffer_type() {
             flexbuffers::FlexBufferType::Null => Ok(Value::Null),
             flexbuffers::FlexBufferType::Bool => Ok(Value::Bool(reader.as_bool())),
-            flexbuffers::FlexBufferType::Int | flexbuffers::FlexBufferType::UInt => {
-                Ok(Value::Number(reader.as_i64().into()))
-            }
+            flexbuffers::FlexBufferType::Int => Ok(Value::Number(reader.as_i64().into())),
+            flexbuffers::FlexBufferType::UInt => Ok(Value::Number(reader.as_u64().into())),
             flexbuffers::FlexBufferType::Float => {
                 let f = reader.as_f64();
                 Ok(Value::Number(
                     serde_json::Number::from_f64(f).unwrap_or(0.into()),
                 ))
             }
-            flexbuffers::FlexBufferType::String => {
-                let s = reader.as_str();
-                // Try to parse as JSON first (for nested structures), fallback to string
-                if let Ok(json_value) = serde_json::from_str::<Value>(s) {
-                    match json_value {
-                        Value::String(_) => Ok(Value::String(s.to_string())), // It was just a string
-                        other => Ok(other), // It was a serialized structure
-                    }
-                } else {
-                    Ok(Value::String(s.to_string()))
-                }
-            }
-            flexbuffers::FlexBufferType::Vector
-            | flexbuffers::FlexBufferType::VectorInt
-            | flexbuffers::FlexBufferType::VectorUInt
-            | flexbuffers::FlexBufferType::VectorFloat
-            | flexbuffers::FlexBufferType::VectorBool
-            | flexbuffers::FlexBufferType::VectorKey
-            | flexbuffers::FlexBufferType::VectorString
-            | flexbuffers::FlexBufferType::VectorInt2
-            | flexbuffers::FlexBufferType::VectorInt3
-            | flexbuffers::FlexBufferType::VectorInt4
-            | flexbuffers::FlexBufferType::VectorUInt2
-            | flexbuffers::FlexBufferType::VectorUInt3
-            | flexbuffers::FlexBufferType::VectorUInt4
-            | flexbuffers::FlexBufferType::VectorFloat2
-            | flexbuffers::FlexBufferType::VectorFloat3
-            | flexbuffers::FlexBufferType::VectorFloat4 => {
+            flexbuffers::FlexBufferType::String => Ok(Value::String(reader.as_str().to_string())),
+            t if is_vector_type(t) => {
                 let vec = reader.as_vector();
                 let mut arr = Vec::new();
                 for i in 0..vec.len() {
@@ -196,6 +513,10 @@ impl FlexBuffer {
                 }
                 Ok(Value::Object(obj))
             }
+            flexbuffers::FlexBufferType::Blob => {
+                let blob = reader.as_blob();
+                Ok(Value::String(STANDARD.encode(blob.0)))
+            }
             other => Err(Error::new(
                 Status::GenericFailure,
                 format!("Unsupported flexbuffer type: {:?}", other),
@@ -204,6 +525,332 @@ impl FlexBuffer {
     }
 }
 
+enum OwnedValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Blob(Vec<u8>),
+    Vector(Vec<OwnedValue>),
+    Map(Vec<(String, OwnedValue)>),
+}
+
+enum OwnedFrame {
+    Vector(Vec<OwnedValue>),
+    Map(Vec<(String, OwnedValue)>),
+}
+
+/// A container on the builder's stack together with the key it should be
+/// attached under once closed, if its parent turns out to be a map. Captured
+/// at `start_vector`/`start_map` time, since `pending_key` may be overwritten
+/// by `pushKey` calls for the container's own children before it closes.
+struct StackEntry {
+    frame: OwnedFrame,
+    key: Option<String>,
+}
+
+/// Incremental builder mirroring flexbuffers' own streaming API (startVector/
+/// startMap/push*/end/finish) one call at a time, so a caller never has to
+/// build a `serde_json::Value` tree in one shot. Internally it just
+/// accumulates an owned `OwnedValue` tree on `stack` and drives the existing
+/// `serialize_value`-style recursion through flexbuffers' builder at
+/// `finish()`; an earlier version erased borrowed `VectorBuilder`/
+/// `MapBuilder` lifetimes to `'static` to store them directly, which turned
+/// out to be unsound (their `Drop` impls mutate shared state in the root
+/// `Builder` in ways the borrow checker needs to track).
+#[napi]
+pub struct FlexBuilder {
+    stack: Vec<StackEntry>,
+    root: Option<OwnedValue>,
+    pending_key: Option<String>,
+}
+
+impl Default for FlexBuilder {
+    fn default() -> Self {
+        Self {
+            stack: Vec::new(),
+            root: None,
+            pending_key: None,
+        }
+    }
+}
+
+#[napi]
+impl FlexBuilder {
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[napi]
+    pub fn push_key(&mut self, key: String) {
+        self.pending_key = Some(key);
+    }
+
+    #[napi]
+    pub fn start_vector(&mut self) -> Result<()> {
+        let key = self.take_key_for_new_container()?;
+        self.stack.push(StackEntry {
+            frame: OwnedFrame::Vector(Vec::new()),
+            key,
+        });
+        Ok(())
+    }
+
+    #[napi]
+    pub fn start_map(&mut self) -> Result<()> {
+        let key = self.take_key_for_new_container()?;
+        self.stack.push(StackEntry {
+            frame: OwnedFrame::Map(Vec::new()),
+            key,
+        });
+        Ok(())
+    }
+
+    #[napi]
+    pub fn end(&mut self) -> Result<()> {
+        let entry = self
+            .stack
+            .pop()
+            .ok_or_else(|| Error::new(Status::InvalidArg, "No open container to end"))?;
+        // A key staged with pushKey() but never consumed belongs to the container
+        // that just closed; carrying it over would let it get silently reused as
+        // the key for the next map value instead of raising the usual error.
+        self.pending_key = None;
+
+        let value = match entry.frame {
+            OwnedFrame::Vector(items) => OwnedValue::Vector(items),
+            OwnedFrame::Map(pairs) => OwnedValue::Map(pairs),
+        };
+        self.attach(value, entry.key)
+    }
+
+    #[napi]
+    pub fn push_string(&mut self, value: String) -> Result<()> {
+        self.push_owned(OwnedValue::String(value))
+    }
+
+    #[napi]
+    pub fn push_i64(&mut self, value: i64) -> Result<()> {
+        self.push_owned(OwnedValue::Int(value))
+    }
+
+    #[napi]
+    pub fn push_f64(&mut self, value: f64) -> Result<()> {
+        self.push_owned(OwnedValue::Float(value))
+    }
+
+    #[napi]
+    pub fn push_bool(&mut self, value: bool) -> Result<()> {
+        self.push_owned(OwnedValue::Bool(value))
+    }
+
+    #[napi]
+    pub fn push_null(&mut self) -> Result<()> {
+        self.push_owned(OwnedValue::Null)
+    }
+
+    #[napi]
+    pub fn push_blob(&mut self, data: Buffer) -> Result<()> {
+        self.push_owned(OwnedValue::Blob(data.to_vec()))
+    }
+
+    #[napi]
+    pub fn finish(&mut self) -> Result<Vec<u8>> {
+        if !self.stack.is_empty() {
+            return Err(Error::new(
+                Status::InvalidArg,
+                "Cannot finish while containers are still open",
+            ));
+        }
+
+        let root = self
+            .root
+            .take()
+            .ok_or_else(|| Error::new(Status::InvalidArg, "No value has been pushed"))?;
+        self.pending_key = None;
+
+        let mut builder = flexbuffers::Builder::default();
+        Self::build_owned(&mut builder, &root);
+        Ok(builder.take_buffer())
+    }
+
+    fn take_key_for_new_container(&mut self) -> Result<Option<String>> {
+        match self.stack.last() {
+            Some(StackEntry {
+                frame: OwnedFrame::Map(pairs),
+                ..
+            }) => {
+                let key = self.pending_key.take().ok_or_else(|| {
+                    Error::new(
+                        Status::InvalidArg,
+                        "pushKey must be called before starting a nested container inside a map",
+                    )
+                })?;
+                Self::check_duplicate_key(pairs, &key)?;
+                Ok(Some(key))
+            }
+            Some(StackEntry {
+                frame: OwnedFrame::Vector(_),
+                ..
+            })
+            | None => Ok(None),
+        }
+    }
+
+    fn push_owned(&mut self, value: OwnedValue) -> Result<()> {
+        match self.stack.last_mut() {
+            None => {
+                if self.root.is_some() {
+                    return Err(Error::new(
+                        Status::InvalidArg,
+                        "Document already has a root value",
+                    ));
+                }
+                self.root = Some(value);
+                Ok(())
+            }
+            Some(entry) => match &mut entry.frame {
+                OwnedFrame::Vector(items) => {
+                    items.push(value);
+                    Ok(())
+                }
+                OwnedFrame::Map(pairs) => {
+                    let key = self.pending_key.take().ok_or_else(|| {
+                        Error::new(
+                            Status::InvalidArg,
+                            "pushKey must be called before pushing a map value",
+                        )
+                    })?;
+                    Self::check_duplicate_key(pairs, &key)?;
+                    pairs.push((key, value));
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    fn attach(&mut self, value: OwnedValue, key: Option<String>) -> Result<()> {
+        match self.stack.last_mut() {
+            None => {
+                self.root = Some(value);
+                Ok(())
+            }
+            Some(entry) => match &mut entry.frame {
+                OwnedFrame::Vector(items) => {
+                    items.push(value);
+                    Ok(())
+                }
+                OwnedFrame::Map(pairs) => {
+                    let key = key.ok_or_else(|| {
+                        Error::new(Status::GenericFailure, "Map child is missing its key")
+                    })?;
+                    pairs.push((key, value));
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    fn check_duplicate_key(pairs: &[(String, OwnedValue)], key: &str) -> Result<()> {
+        if pairs.iter().any(|(k, _)| k == key) {
+            return Err(Error::new(
+                Status::InvalidArg,
+                format!("Duplicate key in map: {}", key),
+            ));
+        }
+        Ok(())
+    }
+
+    fn build_owned(builder: &mut flexbuffers::Builder, value: &OwnedValue) {
+        match value {
+            OwnedValue::Null => {
+                builder.build_singleton(());
+            }
+            OwnedValue::Bool(b) => {
+                builder.build_singleton(*b);
+            }
+            OwnedValue::Int(i) => {
+                builder.build_singleton(*i);
+            }
+            OwnedValue::Float(f) => {
+                builder.build_singleton(*f);
+            }
+            OwnedValue::String(s) => {
+                builder.build_singleton(s.as_str());
+            }
+            OwnedValue::Blob(bytes) => {
+                builder.build_singleton(flexbuffers::Blob(bytes.as_slice()));
+            }
+            OwnedValue::Vector(items) => {
+                let mut vec = builder.start_vector();
+                for item in items {
+                    Self::build_owned_into_vector(&mut vec, item);
+                }
+                vec.end_vector();
+            }
+            OwnedValue::Map(pairs) => {
+                let mut map = builder.start_map();
+                for (key, val) in pairs {
+                    Self::build_owned_into_map(&mut map, key, val);
+                }
+                map.end_map();
+            }
+        }
+    }
+
+    fn build_owned_into_vector(vec: &mut flexbuffers::VectorBuilder, value: &OwnedValue) {
+        match value {
+            OwnedValue::Null => vec.push(()),
+            OwnedValue::Bool(b) => vec.push(*b),
+            OwnedValue::Int(i) => vec.push(*i),
+            OwnedValue::Float(f) => vec.push(*f),
+            OwnedValue::String(s) => vec.push(s.as_str()),
+            OwnedValue::Blob(bytes) => vec.push(flexbuffers::Blob(bytes.as_slice())),
+            OwnedValue::Vector(items) => {
+                let mut child = vec.start_vector();
+                for item in items {
+                    Self::build_owned_into_vector(&mut child, item);
+                }
+                child.end_vector();
+            }
+            OwnedValue::Map(pairs) => {
+                let mut child = vec.start_map();
+                for (key, val) in pairs {
+                    Self::build_owned_into_map(&mut child, key, val);
+                }
+                child.end_map();
+            }
+        }
+    }
+
+    fn build_owned_into_map(map: &mut flexbuffers::MapBuilder, key: &str, value: &OwnedValue) {
+        match value {
+            OwnedValue::Null => map.push(key, ()),
+            OwnedValue::Bool(b) => map.push(key, *b),
+            OwnedValue::Int(i) => map.push(key, *i),
+            OwnedValue::Float(f) => map.push(key, *f),
+            OwnedValue::String(s) => map.push(key, s.as_str()),
+            OwnedValue::Blob(bytes) => map.push(key, flexbuffers::Blob(bytes.as_slice())),
+            OwnedValue::Vector(items) => {
+                let mut child = map.start_vector(key);
+                for item in items {
+                    Self::build_owned_into_vector(&mut child, item);
+                }
+                child.end_vector();
+            }
+            OwnedValue::Map(pairs) => {
+                let mut child = map.start_map(key);
+                for (k, v) in pairs {
+                    Self::build_owned_into_map(&mut child, k, v);
+                }
+                child.end_map();
+            }
+        }
+    }
+}
+
 #[napi]
 pub fn serialize(value: serde_json::Value) -> Result<Vec<u8>> {
     let mut fb = FlexBuffer::new();
@@ -217,7 +864,134 @@ pub fn deserialize(buffer: Vec<u8>) -> Result<serde_json::Value> {
     fb.deserialize()
 }
 
+#[napi]
+pub fn serialize_with_options(
+    value: serde_json::Value,
+    options: FlexBufferOptions,
+) -> Result<Vec<u8>> {
+    let mut fb = FlexBuffer::new();
+    fb.serialize_with_options(value, options)?;
+    Ok(fb.get_buffer())
+}
+
+#[napi]
+pub fn to_json_string(buffer: Vec<u8>, pretty: Option<bool>) -> Result<String> {
+    let fb = FlexBuffer::from_buffer(buffer)?;
+    fb.to_json_string(pretty)
+}
+
+#[napi]
+pub fn from_json_string(text: String) -> Result<Vec<u8>> {
+    let fb = FlexBuffer::from_json_string(text)?;
+    Ok(fb.get_buffer())
+}
+
 #[napi]
 pub fn is_valid_flexbuffer(buffer: Vec<u8>) -> bool {
     flexbuffers::Reader::get_root(&buffer[..]).is_ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn big_int_round_trip_preserves_large_unsigned_values() {
+        let mut fb = FlexBuffer::new();
+        fb.serialize_big_int(BigInt::from(u64::MAX)).unwrap();
+        let (_, value, lossless) = fb.get_big_int().unwrap().get_u64();
+        assert!(lossless);
+        assert_eq!(value, u64::MAX);
+    }
+
+    #[test]
+    fn big_int_round_trip_preserves_large_signed_values() {
+        let mut fb = FlexBuffer::new();
+        fb.serialize_big_int(BigInt::from(i64::MIN)).unwrap();
+        let (value, lossless) = fb.get_big_int().unwrap().get_i64();
+        assert!(lossless);
+        assert_eq!(value, i64::MIN);
+    }
+
+    #[test]
+    fn blob_round_trip_preserves_bytes() {
+        let mut fb = FlexBuffer::new();
+        let bytes: Buffer = vec![0u8, 1, 2, 255].into();
+        fb.serialize_blob(bytes.clone()).unwrap();
+        assert_eq!(fb.get_blob().unwrap().as_ref(), bytes.as_ref());
+    }
+
+    #[test]
+    fn deserialize_value_base64_encodes_blobs() {
+        let mut fb = FlexBuffer::new();
+        fb.serialize_blob(vec![1u8, 2, 3].into()).unwrap();
+        let value = fb.deserialize().unwrap();
+        assert_eq!(value, Value::String(STANDARD.encode([1, 2, 3])));
+    }
+
+    #[test]
+    fn length_reports_blob_byte_count() {
+        let mut fb = FlexBuffer::new();
+        fb.serialize_blob(vec![1u8, 2, 3].into()).unwrap();
+        assert_eq!(fb.length(vec![]).unwrap(), 3);
+    }
+
+    #[test]
+    fn to_json_string_base64_encodes_blobs() {
+        let mut fb = FlexBuffer::new();
+        fb.serialize_blob(vec![1u8, 2, 3].into()).unwrap();
+        let json = fb.to_json_string(Some(false)).unwrap();
+        assert_eq!(json, format!("\"{}\"", STANDARD.encode([1, 2, 3])));
+    }
+
+    #[test]
+    fn flex_builder_does_not_leak_a_stale_key_across_containers() {
+        let mut builder = FlexBuilder::new();
+        builder.start_map().unwrap();
+        builder.push_key("a".to_string());
+        builder.end().unwrap(); // closed without consuming the staged key
+
+        builder.start_map().unwrap();
+        let err = builder.push_i64(1).unwrap_err();
+        assert!(err.reason.contains("pushKey must be called"));
+    }
+
+    #[test]
+    fn flex_builder_rejects_duplicate_keys_in_the_same_map() {
+        let mut builder = FlexBuilder::new();
+        builder.start_map().unwrap();
+        builder.push_key("a".to_string());
+        builder.push_i64(1).unwrap();
+        builder.push_key("a".to_string());
+        let err = builder.push_i64(2).unwrap_err();
+        assert!(err.reason.contains("Duplicate key"));
+    }
+
+    // Exercises FlexBuilder's container stack (start_vector/start_map/end)
+    // three levels deep: vector -> map -> vector, mixing both frame kinds,
+    // then round-trips the result back through FlexBuffer::deserialize.
+    #[test]
+    fn flex_builder_round_trips_multi_level_nesting() {
+        let mut builder = FlexBuilder::new();
+        builder.start_vector().unwrap();
+        builder.push_i64(1).unwrap();
+        builder.start_map().unwrap();
+        builder.push_key("nested".to_string());
+        builder.start_vector().unwrap();
+        builder.push_string("a".to_string()).unwrap();
+        builder.push_bool(true).unwrap();
+        builder.push_null().unwrap();
+        builder.end().unwrap(); // inner vector
+        builder.end().unwrap(); // map
+        builder.push_f64(2.5).unwrap();
+        builder.end().unwrap(); // outer vector
+        let buffer = builder.finish().unwrap();
+
+        let fb = FlexBuffer::from_buffer(buffer).unwrap();
+        let value = fb.deserialize().unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([1, { "nested": ["a", true, null] }, 2.5])
+        );
+    }
+}